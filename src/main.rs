@@ -6,9 +6,11 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use std::io;
-use std::io::BufRead;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::collections::HashSet;
+use std::os::unix::fs::MetadataExt;
 // `tui` is a library for building Text User Interfaces (TUIs)
 use tui::{
     backend::CrosstermBackend, // Connects `tui` with `crossterm` for terminal backend operations.
@@ -21,53 +23,514 @@ use tui::{
 // for recursively traversing filesystem
 use walkdir::WalkDir;
 
-struct FileSystemEntry {
-    path: String,
+// Which panel the left pane is currently showing.
+enum View {
+    Browser,
+    Filesystems,
+}
+
+// Whether the left pane is taking normal navigation keys or capturing text
+// typed into the incremental filter.
+enum Mode {
+    Normal,
+    Filtering,
+}
+
+// Rebuilds the tree from disk, re-expanding whatever directories were
+// expanded in `tree` so a periodic refresh doesn't collapse the user's view.
+fn refresh_tree(current_path: &str, tree: &[TreeNode]) -> Vec<TreeNode> {
+    let expanded_dirs: HashSet<PathBuf> = tree
+        .iter()
+        .filter(|node| node.expanded)
+        .map(|node| node.path.clone())
+        .collect();
+
+    let mut refreshed = list_children(Path::new(current_path), 0);
+    let mut i = 0;
+    while i < refreshed.len() {
+        if refreshed[i].is_dir && expanded_dirs.contains(&refreshed[i].path) {
+            let depth = refreshed[i].depth;
+            let children = list_children(&refreshed[i].path, depth + 1);
+            refreshed[i].expanded = true;
+            for (offset, child) in children.into_iter().enumerate() {
+                refreshed.insert(i + 1 + offset, child);
+            }
+        }
+        i += 1;
+    }
+    refreshed
+}
+
+// Returns the indices into `tree` whose file name contains `filter`
+// (case-insensitive). An empty filter matches everything.
+fn visible_indices(tree: &[TreeNode], filter: &str) -> Vec<usize> {
+    if filter.is_empty() {
+        return (0..tree.len()).collect();
+    }
+    let needle = filter.to_lowercase();
+    tree.iter()
+        .enumerate()
+        .filter(|(_, node)| {
+            node.path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_lowercase().contains(&needle))
+                .unwrap_or(false)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// A single row of the flattened tree. The tree itself is never stored as a
+// recursive structure; instead `expanded` directories have their immediate
+// children spliced directly into the surrounding `Vec<TreeNode>` so that
+// `selected`/`scroll` keep working exactly as they did for the old flat list.
+struct TreeNode {
+    path: PathBuf,
+    depth: u8,
     is_dir: bool,
+    expanded: bool,
 }
 
-fn list_directory_contents(path: &str) -> Vec<FileSystemEntry> {
+// Lists the immediate children of `path`, tagging them with `depth` so they
+// can be spliced into the display list at the right indentation level.
+fn list_children(path: &Path, depth: u8) -> Vec<TreeNode> {
     WalkDir::new(path)
         .min_depth(1) // Start at depth 1 to skip the root directory itself.
         .max_depth(1) // Limit traversal to the immediate contents of the directory, not going deeper.
         .into_iter()
         .filter_map(Result::ok) // Filter out any errors
-        // .filter(|e| e.file_type().is_file()) 
-        .map(|e| FileSystemEntry {
-            path: e.path().display().to_string(),
+        .map(|e| TreeNode {
+            path: e.path().to_path_buf(),
+            depth,
             is_dir: e.file_type().is_dir(),
+            expanded: false,
         })
         .collect()
 }
 
-fn read_file_preview(path: &str) -> String {
-    const MAX_LINES: usize = 20; // Limit the preview to 10 lines
-    let file = std::fs::File::open(path);
-    match file {
-        Ok(file) => {
-            let reader = std::io::BufReader::new(file);
-            let lines: Vec<_> = reader.lines()
-                                    .take(MAX_LINES)
-                                    .collect::<Result<_, _>>()
-                                    .unwrap_or_else(|_| vec!["Error reading file".to_string()]);
-            lines.join("\n")
+// Toggles the directory at `idx` open or closed, splicing or removing its
+// children in place so the rest of the tree doesn't have to move around.
+fn toggle_expand(tree: &mut Vec<TreeNode>, idx: usize) {
+    let node_depth = tree[idx].depth;
+    if tree[idx].expanded {
+        // Collapse: drop the contiguous run of rows deeper than this node.
+        let mut end = idx + 1;
+        while end < tree.len() && tree[end].depth > node_depth {
+            end += 1;
+        }
+        tree.drain(idx + 1..end);
+        tree[idx].expanded = false;
+    } else {
+        let children = list_children(&tree[idx].path, node_depth + 1);
+        tree[idx].expanded = true;
+        for (offset, child) in children.into_iter().enumerate() {
+            tree.insert(idx + 1 + offset, child);
         }
-        Err(_) => "Cannot open file".to_string(),
     }
 }
 
+// What the right pane should render for the currently selected file, decided
+// by sniffing the first few KB rather than always dumping raw lines.
+enum Preview {
+    Text(Vec<Spans<'static>>),
+    Hex(String),
+    Empty(String),
+}
+
+const PREVIEW_SAMPLE_SIZE: usize = 4096;
+const PREVIEW_MAX_LINES: usize = 20;
+
+// A batch operation queued against the flagged set, waiting on a destination
+// path typed into the bottom input line.
+enum PendingOp {
+    Copy,
+    Move,
+}
+
+// Recursively copies `src` to `dest`, descending into directories since
+// `fs::copy` only handles regular files.
+fn copy_recursive(src: &Path, dest: &Path) -> io::Result<()> {
+    if src.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(src, dest).map(|_| ())
+    }
+}
+
+// Moves `src` to `dest`, falling back to a recursive copy + remove when
+// `fs::rename` fails (e.g. `EXDEV` for a move across filesystems).
+fn move_path(src: &Path, dest: &Path) -> io::Result<()> {
+    if fs::rename(src, dest).is_ok() {
+        return Ok(());
+    }
+    copy_recursive(src, dest)?;
+    if src.is_dir() {
+        fs::remove_dir_all(src)
+    } else {
+        fs::remove_file(src)
+    }
+}
+
+// Copies or moves every flagged path into `destination`, keeping each file's
+// original name. Best-effort: a failure on one path doesn't stop the rest,
+// but every failure is returned so the caller can surface it to the user.
+fn execute_batch_op(op: &PendingOp, flagged: &HashSet<PathBuf>, destination: &str) -> Vec<String> {
+    let dest_dir = PathBuf::from(destination);
+    let mut errors = Vec::new();
+    for src in flagged {
+        let Some(file_name) = src.file_name() else {
+            errors.push(format!("{}: has no file name", src.display()));
+            continue;
+        };
+        let dest_path = dest_dir.join(file_name);
+        let result = match op {
+            PendingOp::Copy => copy_recursive(src, &dest_path),
+            PendingOp::Move => move_path(src, &dest_path),
+        };
+        if let Err(err) = result {
+            errors.push(format!("{}: {}", src.display(), err));
+        }
+    }
+    errors
+}
+
+// Deletes every flagged path, recursing into directories.
+fn delete_flagged(flagged: &HashSet<PathBuf>) {
+    for path in flagged {
+        if path.is_dir() {
+            let _ = fs::remove_dir_all(path);
+        } else {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+// Sniffs the first few KB of `path` to decide whether it's text or binary,
+// then builds the appropriate `Preview` for the right pane to render. Only
+// a bounded prefix is ever read off disk, so a multi-GB file costs the same
+// as a tiny one.
+fn read_file_preview(path: &str) -> Preview {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(_) => return Preview::Empty("Cannot open file".to_string()),
+    };
+    let mut sample = Vec::with_capacity(PREVIEW_SAMPLE_SIZE);
+    let read_result = std::io::BufReader::new(file)
+        .take(PREVIEW_SAMPLE_SIZE as u64)
+        .read_to_end(&mut sample);
+    if read_result.is_err() {
+        return Preview::Empty("Error reading file".to_string());
+    }
+    if sample.is_empty() {
+        return Preview::Empty("(empty file)".to_string());
+    }
+
+    // A multibyte character cut off by the sample boundary is not a sign of
+    // binary data, so only an error with a known length counts as invalid.
+    let is_binary = sample.contains(&0)
+        || matches!(std::str::from_utf8(&sample), Err(e) if e.error_len().is_some());
+
+    if is_binary {
+        Preview::Hex(hex_dump(&sample, PREVIEW_MAX_LINES))
+    } else {
+        let text = String::from_utf8_lossy(&sample);
+        let ext = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+        let lines = text
+            .lines()
+            .take(PREVIEW_MAX_LINES)
+            .map(|line| highlight_line(line, ext))
+            .collect();
+        Preview::Text(lines)
+    }
+}
+
+// Renders `bytes` as an offset/hex/ASCII dump, 16 bytes per row, like `xxd`.
+fn hex_dump(bytes: &[u8], max_rows: usize) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(16).take(max_rows).enumerate() {
+        let offset = row * 16;
+        let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+            .collect();
+        out.push_str(&format!("{:08x}  {:<47}  {}\n", offset, hex.join(" "), ascii));
+    }
+    out
+}
+
+// Returns the keyword list used to colorize a file of the given extension.
+fn keywords_for(ext: &str) -> &'static [&'static str] {
+    match ext {
+        "rs" => &[
+            "fn", "let", "mut", "struct", "enum", "impl", "pub", "use", "match", "if", "else",
+            "for", "while", "loop", "return", "break", "continue", "const", "static", "trait",
+            "mod", "as", "self", "Self", "true", "false",
+        ],
+        "py" => &[
+            "def", "class", "import", "from", "as", "if", "elif", "else", "for", "while",
+            "return", "break", "continue", "pass", "with", "try", "except", "finally", "lambda",
+            "True", "False", "None",
+        ],
+        "js" | "ts" => &[
+            "function", "const", "let", "var", "if", "else", "for", "while", "return", "break",
+            "continue", "class", "import", "export", "from", "new", "this", "true", "false",
+            "null",
+        ],
+        _ => &[],
+    }
+}
+
+// Tokenizes a single line into colored spans: comments, strings, numbers,
+// and keywords get their own `Style`, everything else is left as-is.
+fn highlight_line(line: &str, ext: &str) -> Spans<'static> {
+    let comment_prefix = match ext {
+        "py" | "sh" | "toml" | "yaml" | "yml" => "#",
+        _ => "//",
+    };
+    if line.trim_start().starts_with(comment_prefix) {
+        return Spans::from(vec![Span::styled(
+            line.to_string(),
+            Style::default().fg(Color::DarkGray),
+        )]);
+    }
+
+    let keywords = keywords_for(ext);
+    let mut spans = Vec::new();
+    let mut buf = String::new();
+    let mut in_string: Option<char> = None;
+
+    macro_rules! flush_word {
+        () => {
+            if !buf.is_empty() {
+                let style = if keywords.contains(&buf.as_str()) {
+                    Style::default().fg(Color::Magenta)
+                } else if buf.chars().all(|c| c.is_ascii_digit()) {
+                    Style::default().fg(Color::Cyan)
+                } else {
+                    Style::default()
+                };
+                spans.push(Span::styled(buf.clone(), style));
+                buf.clear();
+            }
+        };
+    }
+
+    for c in line.chars() {
+        if let Some(quote) = in_string {
+            buf.push(c);
+            if c == quote {
+                spans.push(Span::styled(buf.clone(), Style::default().fg(Color::Yellow)));
+                buf.clear();
+                in_string = None;
+            }
+            continue;
+        }
+        if c == '"' || c == '\'' {
+            flush_word!();
+            in_string = Some(c);
+            buf.push(c);
+            continue;
+        }
+        if c.is_alphanumeric() || c == '_' {
+            buf.push(c);
+        } else {
+            flush_word!();
+            spans.push(Span::raw(c.to_string()));
+        }
+    }
+    if in_string.is_some() {
+        spans.push(Span::styled(buf.clone(), Style::default().fg(Color::Yellow)));
+    } else {
+        flush_word!();
+    }
+    Spans::from(spans)
+}
+
+// Maps the `S_IFMT` bits of a mode to the single-letter type column used in
+// `ls -l`/`stat` output.
+fn file_type_char(mode: u32) -> char {
+    match mode & libc::S_IFMT {
+        libc::S_IFDIR => 'd',
+        libc::S_IFLNK => 'l',
+        libc::S_IFCHR => 'c',
+        libc::S_IFBLK => 'b',
+        libc::S_IFIFO => 'p',
+        libc::S_IFSOCK => 's',
+        _ => '-',
+    }
+}
+
+// The human-readable counterpart of `file_type_char`, for the "Type:" line.
+fn file_type_name(mode: u32) -> &'static str {
+    match mode & libc::S_IFMT {
+        libc::S_IFDIR => "directory",
+        libc::S_IFLNK => "symbolic link",
+        libc::S_IFCHR => "character special file",
+        libc::S_IFBLK => "block special file",
+        libc::S_IFIFO => "fifo",
+        libc::S_IFSOCK => "socket",
+        _ => "regular file",
+    }
+}
+
+// Renders a mode's permission bits as a `-rwxr-xr-x`-style string.
+fn permissions_string(mode: u32) -> String {
+    let perm = |bit: u32, ch: char| if mode & bit != 0 { ch } else { '-' };
+    format!(
+        "{}{}{}{}{}{}{}{}{}{}",
+        file_type_char(mode),
+        perm(0o400, 'r'),
+        perm(0o200, 'w'),
+        perm(0o100, 'x'),
+        perm(0o040, 'r'),
+        perm(0o020, 'w'),
+        perm(0o010, 'x'),
+        perm(0o004, 'r'),
+        perm(0o002, 'w'),
+        perm(0o001, 'x'),
+    )
+}
+
+fn format_timestamp(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// Builds a `stat`-style breakdown of `path`: permissions, type, owner/group,
+// link count, inode, size, and the three Unix timestamps. Symlinks are
+// reported with their resolved target rather than being followed.
 fn generate_file_info(path: &str) -> String {
-    let metadata = fs::metadata(path);
-    match metadata {
-        Ok(metadata) => {
-            let size = metadata.len();
-            let modified = metadata.modified().unwrap_or_else(|_| std::time::SystemTime::now());
-            let modified_date = modified.duration_since(std::time::UNIX_EPOCH).expect("Time went backwards").as_secs();
-            let modified_time = chrono::NaiveDateTime::from_timestamp(modified_date as i64, 0);
-            format!("Name: {}\nSize: {} bytes\nModified: {}", path, size, modified_time)
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return "Cannot retrieve file info".to_string(),
+    };
+
+    let mode = metadata.mode();
+    let owner = users::get_user_by_uid(metadata.uid())
+        .map(|u| u.name().to_string_lossy().into_owned())
+        .unwrap_or_else(|| metadata.uid().to_string());
+    let group = users::get_group_by_gid(metadata.gid())
+        .map(|g| g.name().to_string_lossy().into_owned())
+        .unwrap_or_else(|| metadata.gid().to_string());
+
+    let mut info = format!(
+        "Name: {}\nType: {}\nPermissions: {}\nOwner: {}\nGroup: {}\nLinks: {}\nInode: {}\nSize: {} ({} bytes)\nBlocks: {}\nAccessed: {}\nModified: {}\nChanged: {}",
+        path,
+        file_type_name(mode),
+        permissions_string(mode),
+        owner,
+        group,
+        metadata.nlink(),
+        metadata.ino(),
+        human_size(metadata.size()),
+        metadata.size(),
+        metadata.blocks(),
+        format_timestamp(metadata.atime()),
+        format_timestamp(metadata.mtime()),
+        format_timestamp(metadata.ctime()),
+    );
+
+    if metadata.file_type().is_symlink() {
+        if let Ok(target) = fs::read_link(path) {
+            info.push_str(&format!("\nTarget: {}", target.display()));
         }
-        Err(_) => "Cannot retrieve file info".to_string(),
     }
+
+    info
+}
+
+// One row of the `:filesystems` panel.
+struct MountInfo {
+    dev: String,
+    mount_point: String,
+    fs_type: String,
+    total: u64,
+    used: u64,
+    available: u64,
+}
+
+// Formats a byte count using the largest unit that keeps it above 1, e.g.
+// `4.2GiB`, mirroring `human_size`-style helpers in coreutils-alikes.
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1}{}", size, UNITS[unit])
+    }
+}
+
+// Queries `statvfs` for the total/used/available capacity of the filesystem
+// mounted at `mount_point`, in bytes. `available` (from `f_bavail`) is the
+// space an unprivileged user can actually use, which is usually less than
+// the raw free space (`f_bfree`) because of the reserved-for-root margin.
+fn statvfs_usage(mount_point: &str) -> Option<(u64, u64, u64)> {
+    let c_path = std::ffi::CString::new(mount_point).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) };
+    if rc != 0 {
+        return None;
+    }
+    let block_size = stat.f_frsize as u64;
+    let total = stat.f_blocks as u64 * block_size;
+    let free = stat.f_bfree as u64 * block_size;
+    let available = stat.f_bavail as u64 * block_size;
+    Some((total, total.saturating_sub(free), available))
+}
+
+// Parses `/proc/mounts` and pairs each entry with its `statvfs` capacity.
+fn list_mounted_filesystems() -> Vec<MountInfo> {
+    let contents = fs::read_to_string("/proc/mounts").unwrap_or_default();
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let dev = fields.next()?.to_string();
+            let mount_point = fields.next()?.to_string();
+            let fs_type = fields.next()?.to_string();
+            let (total, used, available) = statvfs_usage(&mount_point).unwrap_or((0, 0, 0));
+            Some(MountInfo {
+                dev,
+                mount_point,
+                fs_type,
+                total,
+                used,
+                available,
+            })
+        })
+        .collect()
+}
+
+// Renders a fixed-width `[####....]` usage bar for a mount's used/total ratio.
+fn usage_bar(used: u64, total: u64) -> String {
+    const WIDTH: usize = 20;
+    if total == 0 {
+        return format!("[{}]   0%", " ".repeat(WIDTH));
+    }
+    let ratio = used as f64 / total as f64;
+    let filled = ((ratio * WIDTH as f64).round() as usize).min(WIDTH);
+    format!(
+        "[{}{}] {:>3}%",
+        "#".repeat(filled),
+        " ".repeat(WIDTH - filled),
+        (ratio * 100.0).round() as u64
+    )
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -81,20 +544,41 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let path = ".";
     let initial_path = String::from(".");
     let mut current_path = initial_path.clone();
-    let mut files = list_directory_contents(path);
+    let mut tree = list_children(Path::new(path), 0);
     let mut selected = 0;
     let mut scroll: usize = 0; // Tracks the topmost item in the list view
     let display_count = 20; // Example fixed value, adjust based on your UI layout
 
+    let mut view = View::Browser;
+    let mut mounts: Vec<MountInfo> = Vec::new();
+    let mut mount_selected = 0;
+
+    let mut flagged: HashSet<PathBuf> = HashSet::new();
+    let mut pending_op: Option<PendingOp> = None;
+    let mut op_input = String::new();
+    let mut op_error: Option<String> = None;
+
+    let mut mode = Mode::Normal;
+    let mut filter_buf = String::new();
 
+    // Redraw on a fixed tick as well as on keypresses, so the UI isn't frozen
+    // between them and the listing stays current with the filesystem.
+    let tick_rate = std::time::Duration::from_millis(200);
+    let mut last_tick = std::time::Instant::now();
 
     loop {
         terminal.draw(|f| {
+            // Reserve a one-line status/input bar along the bottom of the screen.
+            let outer_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+                .split(f.size());
+
             let chunks = Layout::default()
                 .direction(Direction::Horizontal)
                 .margin(2)
                 .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-                .split(f.size());
+                .split(outer_chunks[0]);
                 // Split the right side into two vertically
             let right_chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -104,120 +588,520 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             ])
             .split(chunks[1]);
 
-                // Use the current directory name or the initial path as the title
-                let current_dir_name = Path::new(&current_path)
-                    .file_name()
-                    .map(|name| name.to_string_lossy().into_owned())
-                    .unwrap_or_else(|| {
-                        Path::new(&current_path)
-                            .components()
-                            .last()
-                            .map(|c| c.as_os_str().to_string_lossy().into_owned())
-                            .unwrap_or_else(|| "Directory".into())
-                    });
-
-                    let items: Vec<ListItem> = files
-                    .iter()
-                    .skip(scroll)
-                    .take(display_count)
-                    .enumerate()
-                    .map(|(i, file)| {
-                        // Adjust the index to be relative to the start of the displayed list
-                        let display_index = i + scroll;
-                        // Extract just the file name or directory name for display, instead of the full path.
-                        let file_name = Path::new(&file.path)
-                            .file_name() // Extracts the last component of the path as a file name
-                            .unwrap_or_else(|| std::ffi::OsStr::new("Unknown")) // Fallback in case of an error
-                            .to_string_lossy(); // Converts the file name to a string
-                
-                        let display_text = if file.is_dir { format!("{}/", file_name) } else { file_name.into_owned() };
-                
-                        // Create a Span from the adjusted display text.
-                        let content = Spans::from(vec![Span::raw(display_text)]);
-                        // Create a ListItem with the content, applying style based on selection or directory status.
-                        let mut item = ListItem::new(content);
-                        if display_index == selected {
-                            item = item.style(Style::default().bg(Color::Blue))
-                        } else if file.is_dir {
-                            item = item.style(Style::default().fg(Color::Green))
-                        }
-                        item
-                    })
-                    .collect();
-                
-
-            let files_list =
-                List::new(items).block(Block::default().borders(Borders::ALL).title(current_dir_name));
-
-                let preview_content = if files[selected].is_dir {
-                    "Directory selected - no preview available".to_string()
-                } else {
-                    read_file_preview(&files[selected].path)
-                };
-            
-                let paragraph = tui::widgets::Paragraph::new(preview_content)
-                    .block(Block::default().borders(Borders::ALL).title("Preview"))
-                    .wrap(tui::widgets::Wrap { trim: true });
-                    let file_info_content = generate_file_info(&files[selected].path);
-                let file_info = tui::widgets::Paragraph::new(file_info_content)
-                    .block(Block::default().borders(Borders::ALL).title("File Info"))
-                    .wrap(tui::widgets::Wrap { trim: true });
-            f.render_widget(files_list, chunks[0]);
+            let (left_list, preview_text, file_info_content): (List, tui::text::Text, String) = match view {
+                View::Browser => {
+                    // Use the current directory name or the initial path as the title
+                    let current_dir_name = Path::new(&current_path)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_else(|| {
+                            Path::new(&current_path)
+                                .components()
+                                .last()
+                                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                                .unwrap_or_else(|| "Directory".into())
+                        });
+
+                    let visible = visible_indices(&tree, &filter_buf);
+                    let selected_pos = visible.iter().position(|&i| i == selected).unwrap_or(0);
+
+                    let items: Vec<ListItem> = visible
+                        .iter()
+                        .skip(scroll)
+                        .take(display_count)
+                        .enumerate()
+                        .map(|(i, &tree_idx)| {
+                            let node = &tree[tree_idx];
+                            // Adjust the index to be relative to the start of the displayed list
+                            let display_index = i + scroll;
+                            // Extract just the file name or directory name for display, instead of the full path.
+                            let file_name = node.path
+                                .file_name() // Extracts the last component of the path as a file name
+                                .unwrap_or_else(|| std::ffi::OsStr::new("Unknown")) // Fallback in case of an error
+                                .to_string_lossy(); // Converts the file name to a string
+
+                            let indent = "  ".repeat(node.depth as usize);
+                            let glyph = if node.is_dir {
+                                if node.expanded { "\u{25be} " } else { "\u{25b8} " }
+                            } else {
+                                "  "
+                            };
+                            let is_flagged = flagged.contains(&node.path);
+                            let marker = if is_flagged { "\u{2714} " } else { "" };
+                            let display_text = format!("{}{}{}{}", indent, glyph, marker, file_name);
+
+                            // Create a Span from the adjusted display text.
+                            let content = Spans::from(vec![Span::raw(display_text)]);
+                            // Create a ListItem with the content, applying style based on selection or directory status.
+                            let mut item = ListItem::new(content);
+                            if display_index == selected_pos {
+                                item = item.style(Style::default().bg(Color::Blue))
+                            } else if is_flagged {
+                                item = item.style(Style::default().fg(Color::Yellow))
+                            } else if node.is_dir {
+                                item = item.style(Style::default().fg(Color::Green))
+                            }
+                            item
+                        })
+                        .collect();
+
+                    let files_list = List::new(items)
+                        .block(Block::default().borders(Borders::ALL).title(current_dir_name));
+
+                    let (preview_text, file_info_content) = match tree.get(selected) {
+                        Some(node) => {
+                            let selected_path = node.path.to_string_lossy().into_owned();
+                            let preview_text = if node.is_dir {
+                                tui::text::Text::from("Directory selected - no preview available")
+                            } else {
+                                match read_file_preview(&selected_path) {
+                                    Preview::Text(lines) => tui::text::Text::from(lines),
+                                    Preview::Hex(dump) => tui::text::Text::from(dump),
+                                    Preview::Empty(message) => tui::text::Text::from(message),
+                                }
+                            };
+                            (preview_text, generate_file_info(&selected_path))
+                        }
+                        None => (
+                            tui::text::Text::from("(empty)"),
+                            "(empty)".to_string(),
+                        ),
+                    };
+
+                    (files_list, preview_text, file_info_content)
+                }
+                View::Filesystems => {
+                    let items: Vec<ListItem> = mounts
+                        .iter()
+                        .enumerate()
+                        .map(|(i, mount)| {
+                            let line = format!(
+                                "{:<18} {:<22} {:<6} {} avail {:>8}",
+                                mount.dev,
+                                mount.mount_point,
+                                mount.fs_type,
+                                usage_bar(mount.used, mount.total),
+                                human_size(mount.available)
+                            );
+                            let mut item = ListItem::new(line);
+                            if i == mount_selected {
+                                item = item.style(Style::default().bg(Color::Blue));
+                            }
+                            item
+                        })
+                        .collect();
+
+                    let fs_list = List::new(items)
+                        .block(Block::default().borders(Borders::ALL).title("Filesystems"));
+
+                    let preview_text = tui::text::Text::from(
+                        "Press Enter to browse the selected mount point.\nPress F to return to the file browser.",
+                    );
+                    let file_info_content = mounts
+                        .get(mount_selected)
+                        .map(|mount| {
+                            format!(
+                                "Device: {}\nMount point: {}\nType: {}\nTotal: {}\nUsed: {}\nAvailable: {}",
+                                mount.dev,
+                                mount.mount_point,
+                                mount.fs_type,
+                                human_size(mount.total),
+                                human_size(mount.used),
+                                human_size(mount.available)
+                            )
+                        })
+                        .unwrap_or_else(|| "No mounted filesystems found".to_string());
+
+                    (fs_list, preview_text, file_info_content)
+                }
+            };
+
+            let paragraph = tui::widgets::Paragraph::new(preview_text)
+                .block(Block::default().borders(Borders::ALL).title("Preview"))
+                .wrap(tui::widgets::Wrap { trim: true });
+            let file_info = tui::widgets::Paragraph::new(file_info_content)
+                .block(Block::default().borders(Borders::ALL).title("File Info"))
+                .wrap(tui::widgets::Wrap { trim: true });
+            f.render_widget(left_list, chunks[0]);
             f.render_widget(paragraph, right_chunks[0]);
             f.render_widget(file_info, right_chunks[1]);
+
+            let status_text = if let Some(err) = &op_error {
+                format!("error: {}", err)
+            } else if matches!(mode, Mode::Filtering) {
+                format!("/{}", filter_buf)
+            } else if let Some(op) = &pending_op {
+                let label = match op {
+                    PendingOp::Copy => "copy",
+                    PendingOp::Move => "move",
+                };
+                format!("{} {} flagged file(s) to: {}", label, flagged.len(), op_input)
+            } else if !filter_buf.is_empty() {
+                format!("filter: {} (Esc to clear)", filter_buf)
+            } else if !flagged.is_empty() {
+                format!(
+                    "{} flagged | Space flag  * all  v invert  u/Esc clear  c copy  m move  d delete",
+                    flagged.len()
+                )
+            } else {
+                String::new()
+            };
+            let status_bar = tui::widgets::Paragraph::new(status_text);
+            f.render_widget(status_bar, outer_chunks[1]);
         })?;
 
+        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
+        if event::poll(timeout)? {
         match event::read()? {
-            CEvent::Key(KeyEvent { code, .. }) => match code {
-                KeyCode::Char('q') => {
-                    break;
-                }
-                KeyCode::Down => {
-                    if selected < files.len() - 1 {
-                        selected += 1;
-                        // Ensure the selected item is always visible
-                        if selected >= scroll + display_count {
-                            scroll = selected - display_count + 1; // Adjust scroll to keep the selected item visible
+            CEvent::Key(KeyEvent { code, .. }) => {
+                // While a copy/move destination is being typed, the input
+                // line swallows all keys until it's confirmed or cancelled.
+                if let Some(op) = &pending_op {
+                    match code {
+                        KeyCode::Char(c) => op_input.push(c),
+                        KeyCode::Backspace => {
+                            op_input.pop();
                         }
+                        KeyCode::Enter => {
+                            let errors = execute_batch_op(op, &flagged, &op_input);
+                            op_error = if errors.is_empty() {
+                                None
+                            } else {
+                                Some(format!(
+                                    "{} of {} failed: {}",
+                                    errors.len(),
+                                    flagged.len(),
+                                    errors.join("; ")
+                                ))
+                            };
+                            flagged.clear();
+                            pending_op = None;
+                            op_input.clear();
+                            tree = list_children(Path::new(&current_path), 0);
+                            selected = 0;
+                            scroll = 0;
+                        }
+                        KeyCode::Esc => {
+                            pending_op = None;
+                            op_input.clear();
+                        }
+                        _ => {}
                     }
-                },
-                KeyCode::Up => {
-                    if selected > 0 {
-                        selected -= 1;
-                        if selected < scroll {
-                            scroll = selected; // Scroll up when the selection moves above the current view
+                    continue;
+                }
+
+                // While filtering, keystrokes narrow the buffer instead of
+                // driving navigation.
+                if matches!(mode, Mode::Filtering) {
+                    match code {
+                        KeyCode::Char(c) => filter_buf.push(c),
+                        KeyCode::Backspace => {
+                            filter_buf.pop();
+                        }
+                        KeyCode::Enter => {
+                            mode = Mode::Normal; // Keep the filter applied for navigation.
                         }
+                        KeyCode::Esc => {
+                            mode = Mode::Normal;
+                            filter_buf.clear();
+                        }
+                        _ => {}
+                    }
+                    // Re-clamp both `selected` and `scroll` to the filtered
+                    // set on every keystroke: the match set can shrink out
+                    // from under the current scroll position even when the
+                    // selection itself is still present.
+                    let visible = visible_indices(&tree, &filter_buf);
+                    if !visible.contains(&selected) {
+                        selected = *visible.first().unwrap_or(&0);
+                    }
+                    let selected_pos = visible.iter().position(|&i| i == selected).unwrap_or(0);
+                    if selected_pos < scroll {
+                        scroll = selected_pos;
                     }
-                },                
-                KeyCode::Enter => {
-                    if files[selected].is_dir {
-                        // Logic to display contents of the selected directory
-                        let new_path = format!("{}/{}", current_path, files[selected].path.trim_start_matches("./"));
-                        current_path = new_path;
-                        files = list_directory_contents(&current_path);
-                        selected = 0; // Reset selection in the new directory
+                    if selected_pos >= scroll + display_count {
+                        scroll = selected_pos + 1 - display_count;
                     }
+                    scroll = scroll.min(visible.len().saturating_sub(1));
+                    continue;
+                }
+
+                if code == KeyCode::Char('q') {
+                    break;
                 }
-                KeyCode::Backspace => {
-                    // First, handle the result of canonicalize() to get the canonical path
-                    if let Ok(canonical_path) = Path::new(&current_path).canonicalize() {
-                        // Then, check if the parent of the canonical path exists
-                        if let Some(parent_path) = canonical_path.parent() {
-                            // Convert the parent path to a String
-                            current_path = parent_path.to_string_lossy().into_owned();
-                            // Refresh the directory listing based on the new current path
-                            files = list_directory_contents(&current_path);
-                            selected = 0; // Reset the selection index
+                if code == KeyCode::Char('F') {
+                    view = match view {
+                        View::Browser => {
+                            mounts = list_mounted_filesystems();
+                            mount_selected = 0;
+                            View::Filesystems
                         }
-                    }
-                },                            
-                _ => {}
+                        View::Filesystems => View::Browser,
+                    };
+                    continue;
+                }
+                match view {
+                    View::Browser => match code {
+                        KeyCode::Down => {
+                            let visible = visible_indices(&tree, &filter_buf);
+                            if let Some(pos) = visible.iter().position(|&i| i == selected) {
+                                if pos + 1 < visible.len() {
+                                    selected = visible[pos + 1];
+                                    // Ensure the selected item is always visible
+                                    if pos + 1 >= scroll + display_count {
+                                        scroll = pos + 1 - display_count + 1; // Adjust scroll to keep the selected item visible
+                                    }
+                                }
+                            }
+                        },
+                        KeyCode::Up => {
+                            let visible = visible_indices(&tree, &filter_buf);
+                            if let Some(pos) = visible.iter().position(|&i| i == selected) {
+                                if pos > 0 {
+                                    selected = visible[pos - 1];
+                                    if pos - 1 < scroll {
+                                        scroll = pos - 1; // Scroll up when the selection moves above the current view
+                                    }
+                                }
+                            }
+                        },
+                        KeyCode::Char('/') => {
+                            mode = Mode::Filtering;
+                        }
+                        KeyCode::Enter | KeyCode::Char('z') => {
+                            if let Some(node) = tree.get(selected) {
+                                if node.is_dir {
+                                    toggle_expand(&mut tree, selected);
+                                }
+                            }
+                        }
+                        KeyCode::Backspace => {
+                            // First, handle the result of canonicalize() to get the canonical path
+                            if let Ok(canonical_path) = Path::new(&current_path).canonicalize() {
+                                // Then, check if the parent of the canonical path exists
+                                if let Some(parent_path) = canonical_path.parent() {
+                                    // Convert the parent path to a String
+                                    current_path = parent_path.to_string_lossy().into_owned();
+                                    // Refresh the directory listing based on the new current path
+                                    tree = list_children(Path::new(&current_path), 0);
+                                    selected = 0; // Reset the selection index
+                                    scroll = 0;
+                                }
+                            }
+                        },
+                        KeyCode::Char(' ') => {
+                            if let Some(node) = tree.get(selected) {
+                                let path = node.path.clone();
+                                if !flagged.remove(&path) {
+                                    flagged.insert(path);
+                                }
+                            }
+                        }
+                        KeyCode::Char('*') => {
+                            // Scope "flag everything" to what's actually on
+                            // screen, not hidden/filtered rows.
+                            for idx in visible_indices(&tree, &filter_buf) {
+                                flagged.insert(tree[idx].path.clone());
+                            }
+                        }
+                        KeyCode::Char('v') => {
+                            for idx in visible_indices(&tree, &filter_buf) {
+                                let path = &tree[idx].path;
+                                if !flagged.remove(path) {
+                                    flagged.insert(path.clone());
+                                }
+                            }
+                        }
+                        KeyCode::Char('u') => {
+                            flagged.clear();
+                        }
+                        KeyCode::Esc => {
+                            // Esc backs out of whichever overlay is active:
+                            // an applied filter first, then flags.
+                            if !filter_buf.is_empty() {
+                                filter_buf.clear();
+                            } else {
+                                flagged.clear();
+                            }
+                        }
+                        KeyCode::Char('c') if !flagged.is_empty() => {
+                            pending_op = Some(PendingOp::Copy);
+                            op_input.clear();
+                            op_error = None;
+                        }
+                        KeyCode::Char('m') if !flagged.is_empty() => {
+                            pending_op = Some(PendingOp::Move);
+                            op_input.clear();
+                            op_error = None;
+                        }
+                        KeyCode::Char('d') if !flagged.is_empty() => {
+                            delete_flagged(&flagged);
+                            flagged.clear();
+                            tree = list_children(Path::new(&current_path), 0);
+                            selected = 0;
+                            scroll = 0;
+                        }
+                        _ => {}
+                    },
+                    View::Filesystems => match code {
+                        KeyCode::Down => {
+                            if mount_selected + 1 < mounts.len() {
+                                mount_selected += 1;
+                            }
+                        }
+                        KeyCode::Up => {
+                            if mount_selected > 0 {
+                                mount_selected -= 1;
+                            }
+                        }
+                        KeyCode::Enter => {
+                            if let Some(mount) = mounts.get(mount_selected) {
+                                current_path = mount.mount_point.clone();
+                                tree = list_children(Path::new(&current_path), 0);
+                                selected = 0;
+                                scroll = 0;
+                                view = View::Browser;
+                            }
+                        }
+                        _ => {}
+                    },
+                }
             },
             _ => {}
         }
+        }
+
+        // Re-run the directory listing on every tick so changes made on disk
+        // underneath the user (by another process) show up without a keypress.
+        // The refresh is skipped while the filesystems view is open, while a
+        // filter is being typed, and while a copy/move destination is pending.
+        if last_tick.elapsed() >= tick_rate {
+            if matches!(view, View::Browser) && matches!(mode, Mode::Normal) && pending_op.is_none() {
+                let selected_path = tree.get(selected).map(|node| node.path.clone());
+                tree = refresh_tree(&current_path, &tree);
+                if let Some(path) = selected_path {
+                    if let Some(pos) = tree.iter().position(|node| node.path == path) {
+                        selected = pos;
+                    } else if selected >= tree.len() {
+                        selected = tree.len().saturating_sub(1);
+                    }
+                }
+            }
+            last_tick = std::time::Instant::now();
+        }
     }
 
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     disable_raw_mode()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Gives each test its own scratch directory under the OS temp dir, keyed
+    // by test name + PID so parallel test runs don't collide.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("rusty_read_test_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn toggle_expand_splices_children_in_and_out() {
+        let root = scratch_dir("toggle_expand");
+        fs::create_dir(root.join("sub")).unwrap();
+        fs::write(root.join("sub").join("a.txt"), b"a").unwrap();
+        fs::write(root.join("sub").join("b.txt"), b"b").unwrap();
+        fs::write(root.join("top.txt"), b"top").unwrap();
+
+        let mut tree = list_children(&root, 0);
+        assert_eq!(tree.len(), 2);
+        let sub_idx = tree.iter().position(|n| n.path.ends_with("sub")).unwrap();
+
+        toggle_expand(&mut tree, sub_idx);
+        assert!(tree[sub_idx].expanded);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree[sub_idx + 1].depth, tree[sub_idx].depth + 1);
+        assert_eq!(tree[sub_idx + 2].depth, tree[sub_idx].depth + 1);
+
+        toggle_expand(&mut tree, sub_idx);
+        assert!(!tree[sub_idx].expanded);
+        assert_eq!(tree.len(), 2);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn refresh_tree_reexpands_previously_open_dirs_and_picks_up_new_children() {
+        let root = scratch_dir("refresh_tree");
+        fs::create_dir(root.join("sub")).unwrap();
+        fs::write(root.join("sub").join("a.txt"), b"a").unwrap();
+
+        let mut tree = list_children(&root, 0);
+        let sub_idx = tree.iter().position(|n| n.path.ends_with("sub")).unwrap();
+        toggle_expand(&mut tree, sub_idx);
+        assert_eq!(tree.len(), 2);
+
+        fs::write(root.join("sub").join("b.txt"), b"b").unwrap();
+        let refreshed = refresh_tree(root.to_str().unwrap(), &tree);
+
+        let refreshed_sub_idx = refreshed.iter().position(|n| n.path.ends_with("sub")).unwrap();
+        assert!(refreshed[refreshed_sub_idx].expanded);
+        assert_eq!(refreshed.len(), 3);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn visible_indices_filters_case_insensitively() {
+        let tree = vec![
+            TreeNode { path: PathBuf::from("Foo.txt"), depth: 0, is_dir: false, expanded: false },
+            TreeNode { path: PathBuf::from("bar.rs"), depth: 0, is_dir: false, expanded: false },
+            TreeNode { path: PathBuf::from("foobar.md"), depth: 0, is_dir: false, expanded: false },
+        ];
+        assert_eq!(visible_indices(&tree, ""), vec![0, 1, 2]);
+        assert_eq!(visible_indices(&tree, "foo"), vec![0, 2]);
+        assert_eq!(visible_indices(&tree, "RS"), vec![1]);
+        assert_eq!(visible_indices(&tree, "zzz"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn human_size_picks_largest_sensible_unit() {
+        assert_eq!(human_size(512), "512B");
+        assert_eq!(human_size(2048), "2.0KiB");
+        assert_eq!(human_size(5 * 1024 * 1024), "5.0MiB");
+    }
+
+    #[test]
+    fn permissions_string_matches_ls_style() {
+        assert_eq!(permissions_string(libc::S_IFREG | 0o755), "-rwxr-xr-x");
+        assert_eq!(permissions_string(libc::S_IFREG | 0o644), "-rw-r--r--");
+        assert_eq!(permissions_string(libc::S_IFDIR | 0o755), "drwxr-xr-x");
+    }
+
+    #[test]
+    fn file_type_char_maps_mode_bits() {
+        assert_eq!(file_type_char(libc::S_IFDIR | 0o755), 'd');
+        assert_eq!(file_type_char(libc::S_IFLNK | 0o777), 'l');
+        assert_eq!(file_type_char(libc::S_IFREG | 0o644), '-');
+    }
+
+    #[test]
+    fn hex_dump_formats_offset_hex_and_ascii() {
+        let dump = hex_dump(b"Hi!", 1);
+        assert!(dump.starts_with("00000000"));
+        assert!(dump.contains("48 69 21"));
+        assert!(dump.contains("Hi!"));
+    }
+
+    #[test]
+    fn usage_bar_reports_percentage_and_handles_zero_total() {
+        assert_eq!(usage_bar(0, 0), "[                    ]   0%");
+        assert!(usage_bar(50, 100).contains(" 50%"));
+        assert!(usage_bar(100, 100).contains("100%"));
+    }
+}